@@ -1,20 +1,23 @@
+mod platform;
+mod types;
+
 use std::fmt;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use std::ops::AddAssign;
+use std::net::IpAddr;
 
-use futures::future;
-use futures::stream::{StreamExt, TryStreamExt};
 use ipnet::{IpNet, Ipv4Net, Ipv6Net};
-use netlink_packet_route::address::Nla::Address;
-use netlink_packet_route::rtnl::constants::{AF_INET, AF_INET6};
-use rtnetlink::new_connection;
 use tokio::runtime::Runtime;
 
+#[cfg(not(target_os = "linux"))]
+use platform::{AddressSource, PlatformSource};
+
+pub use types::{AddrEvent, AddrScope, InterfaceAddr, InterfaceFlags};
+
 /// The errors that can occur when interacting with rtnetlink.
 #[derive(Debug)]
 pub enum Error {
     RtNetlink(rtnetlink::Error),
     IoError(std::io::Error),
+    Unsupported(&'static str),
 }
 
 impl std::error::Error for Error {}
@@ -24,6 +27,7 @@ impl fmt::Display for Error {
         match self {
             Self::RtNetlink(e) => write!(fmt, "rtnetlink error: {}", e),
             Self::IoError(e) => write!(fmt, "rtnetlink connection failed: {}", e),
+            Self::Unsupported(msg) => write!(fmt, "unsupported: {}", msg),
         }
     }
 }
@@ -40,131 +44,469 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+impl From<nix::Error> for Error {
+    fn from(e: nix::Error) -> Self {
+        Self::IoError(std::io::Error::from(e))
+    }
+}
+
 /// An alias for `std::result::Result` that uses `Error`
 /// as its error type.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// A persistent handle that reuses a single tokio `Runtime` (and, on
+/// Linux, a single netlink connection) across many queries instead of
+/// paying full runtime and socket setup on every call. The free
+/// functions in this crate share one lazily-created default handle.
+///
+/// `rt` is `None` when the handle was built with [`new_async`], since
+/// blocking on a fresh `Runtime` from within an existing tokio context
+/// panics; such handles only support the `_async` methods.
+///
+/// [`new_async`]: LinkAddrs::new_async
+pub struct LinkAddrs {
+    rt: Option<Runtime>,
+    #[cfg(target_os = "linux")]
+    handle: rtnetlink::Handle,
+}
+
+impl LinkAddrs {
+    /// Creates a new handle, spinning up its own `Runtime` and (on
+    /// Linux) netlink connection.
+    pub fn new() -> Result<Self> {
+        let rt = Runtime::new()?;
+
+        #[cfg(target_os = "linux")]
+        let handle = rt.block_on(platform::linux::new_handle())?;
+
+        Ok(Self {
+            rt: Some(rt),
+            #[cfg(target_os = "linux")]
+            handle,
+        })
+    }
+
+    /// Creates a new handle without spinning up its own `Runtime`.
+    /// Call this from within an existing tokio context; the resulting
+    /// handle only supports the `_async` methods, since the blocking
+    /// ones have no `Runtime` of their own to block on.
+    pub async fn new_async() -> Result<Self> {
+        #[cfg(target_os = "linux")]
+        let handle = platform::linux::new_handle().await?;
+
+        Ok(Self {
+            rt: None,
+            #[cfg(target_os = "linux")]
+            handle,
+        })
+    }
+
+    /// Blocks on `fut` using the owned `Runtime`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this handle was built with [`new_async`](Self::new_async),
+    /// which has no owned `Runtime` to block on; use the `_async`
+    /// methods instead in that case.
+    fn block_on<F: std::future::Future>(&self, fut: F) -> F::Output {
+        self.rt
+            .as_ref()
+            .expect("LinkAddrs built with new_async() only supports the _async methods")
+            .block_on(fut)
+    }
+
+    /// Get all IP addresses of an interface.
+    pub fn addresses(&self, link: String) -> Result<Vec<IpNet>> {
+        self.block_on(self.addresses_async(link))
+    }
+
+    /// Get all IP addresses of an interface, without blocking on a
+    /// dedicated `Runtime`. Call this from within an existing tokio
+    /// context.
+    pub async fn addresses_async(&self, link: String) -> Result<Vec<IpNet>> {
+        self.internal_addresses(Some(link)).await
+    }
+
+    /// Get the IPv4 addresses of an interface.
+    pub fn ipv4_addresses(&self, link: String) -> Result<Vec<Ipv4Net>> {
+        self.block_on(self.ipv4_addresses_async(link))
+    }
+
+    /// Get the IPv4 addresses of an interface, without blocking on a
+    /// dedicated `Runtime`.
+    pub async fn ipv4_addresses_async(&self, link: String) -> Result<Vec<Ipv4Net>> {
+        let addrs = self
+            .addresses_async(link)
+            .await?
+            .iter()
+            .filter_map(|addr| match addr {
+                IpNet::V4(addr) => Some(*addr),
+                IpNet::V6(_) => None,
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get the IPv6 addresses of an interface.
+    pub fn ipv6_addresses(&self, link: String) -> Result<Vec<Ipv6Net>> {
+        self.block_on(self.ipv6_addresses_async(link))
+    }
+
+    /// Get the IPv6 addresses of an interface, without blocking on a
+    /// dedicated `Runtime`.
+    pub async fn ipv6_addresses_async(&self, link: String) -> Result<Vec<Ipv6Net>> {
+        let addrs = self
+            .addresses_async(link)
+            .await?
+            .iter()
+            .filter_map(|addr| match addr {
+                IpNet::V4(_) => None,
+                IpNet::V6(addr) => Some(*addr),
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get all IP addresses of this host.
+    pub fn all_addresses(&self) -> Result<Vec<IpNet>> {
+        self.block_on(self.all_addresses_async())
+    }
+
+    /// Get all IP addresses of this host, without blocking on a
+    /// dedicated `Runtime`.
+    pub async fn all_addresses_async(&self) -> Result<Vec<IpNet>> {
+        self.internal_addresses(None).await
+    }
+
+    /// Get the IPv4 addresses of this host.
+    pub fn all_ipv4_addresses(&self) -> Result<Vec<Ipv4Net>> {
+        self.block_on(self.all_ipv4_addresses_async())
+    }
+
+    /// Get the IPv4 addresses of this host, without blocking on a
+    /// dedicated `Runtime`.
+    pub async fn all_ipv4_addresses_async(&self) -> Result<Vec<Ipv4Net>> {
+        let addrs = self
+            .all_addresses_async()
+            .await?
+            .iter()
+            .filter_map(|addr| match addr {
+                IpNet::V4(addr) => Some(*addr),
+                IpNet::V6(_) => None,
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get the IPv6 addresses of this host.
+    pub fn all_ipv6_addresses(&self) -> Result<Vec<Ipv6Net>> {
+        self.block_on(self.all_ipv6_addresses_async())
+    }
+
+    /// Get the IPv6 addresses of this host, without blocking on a
+    /// dedicated `Runtime`.
+    pub async fn all_ipv6_addresses_async(&self) -> Result<Vec<Ipv6Net>> {
+        let addrs = self
+            .all_addresses_async()
+            .await?
+            .iter()
+            .filter_map(|addr| match addr {
+                IpNet::V4(_) => None,
+                IpNet::V6(addr) => Some(*addr),
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get all addresses of this host that fall into `scope`.
+    pub fn addresses_by_scope(&self, scope: AddrScope) -> Result<Vec<InterfaceAddr>> {
+        self.block_on(self.addresses_by_scope_async(scope))
+    }
+
+    /// Get all addresses of this host that fall into `scope`, without
+    /// blocking on a dedicated `Runtime`.
+    pub async fn addresses_by_scope_async(&self, scope: AddrScope) -> Result<Vec<InterfaceAddr>> {
+        let addrs = self
+            .all_addresses_detailed_async()
+            .await?
+            .into_iter()
+            .filter(|addr| addr.scope() == scope)
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get the IPv6 addresses of an interface, scoped with the
+    /// originating interface index.
+    pub fn ipv6_addresses_scoped(&self, link: String) -> Result<Vec<(Ipv6Net, u32)>> {
+        self.block_on(self.ipv6_addresses_scoped_async(link))
+    }
+
+    /// Get the IPv6 addresses of an interface, scoped with the
+    /// originating interface index, without blocking on a dedicated
+    /// `Runtime`.
+    pub async fn ipv6_addresses_scoped_async(
+        &self,
+        link: String,
+    ) -> Result<Vec<(Ipv6Net, u32)>> {
+        let addrs = self
+            .addresses_detailed_async(link)
+            .await?
+            .into_iter()
+            .filter_map(|addr| match addr.net {
+                IpNet::V6(net) => Some((net, addr.index)),
+                IpNet::V4(_) => None,
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get the IPv6 addresses of this host, scoped with the
+    /// originating interface index.
+    pub fn all_ipv6_addresses_scoped(&self) -> Result<Vec<(Ipv6Net, u32)>> {
+        self.block_on(self.all_ipv6_addresses_scoped_async())
+    }
+
+    /// Get the IPv6 addresses of this host, scoped with the
+    /// originating interface index, without blocking on a dedicated
+    /// `Runtime`.
+    pub async fn all_ipv6_addresses_scoped_async(&self) -> Result<Vec<(Ipv6Net, u32)>> {
+        let addrs = self
+            .all_addresses_detailed_async()
+            .await?
+            .into_iter()
+            .filter_map(|addr| match addr.net {
+                IpNet::V6(net) => Some((net, addr.index)),
+                IpNet::V4(_) => None,
+            })
+            .collect();
+
+        Ok(addrs)
+    }
+
+    /// Get all IP addresses of an interface, plus the interface
+    /// metadata that came with them.
+    pub fn addresses_detailed(&self, link: String) -> Result<Vec<InterfaceAddr>> {
+        self.block_on(self.addresses_detailed_async(link))
+    }
+
+    /// Get all IP addresses of an interface, plus the interface
+    /// metadata that came with them, without blocking on a dedicated
+    /// `Runtime`.
+    pub async fn addresses_detailed_async(&self, link: String) -> Result<Vec<InterfaceAddr>> {
+        self.internal_addresses_detailed(Some(link)).await
+    }
+
+    /// Get all IP addresses of this host, plus the interface metadata
+    /// that came with them.
+    pub fn all_addresses_detailed(&self) -> Result<Vec<InterfaceAddr>> {
+        self.block_on(self.all_addresses_detailed_async())
+    }
+
+    /// Get all IP addresses of this host, plus the interface metadata
+    /// that came with them, without blocking on a dedicated `Runtime`.
+    pub async fn all_addresses_detailed_async(&self) -> Result<Vec<InterfaceAddr>> {
+        self.internal_addresses_detailed(None).await
+    }
+
+    /// Adds `net` to `link`.
+    pub fn add_address(&self, link: String, net: IpNet) -> Result<()> {
+        self.block_on(self.add_address_async(link, net))
+    }
+
+    /// Adds `net` to `link`, without blocking on a dedicated
+    /// `Runtime`.
+    pub async fn add_address_async(&self, link: String, net: IpNet) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            platform::linux::add_address(&self.handle, link, net).await
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (link, net);
+            Err(Error::Unsupported(
+                "add_address is only implemented on Linux",
+            ))
+        }
+    }
+
+    /// Removes `net` from `link`.
+    pub fn del_address(&self, link: String, net: IpNet) -> Result<()> {
+        self.block_on(self.del_address_async(link, net))
+    }
+
+    /// Removes `net` from `link`, without blocking on a dedicated
+    /// `Runtime`.
+    pub async fn del_address_async(&self, link: String, net: IpNet) -> Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            platform::linux::del_address(&self.handle, link, net).await
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (link, net);
+            Err(Error::Unsupported(
+                "del_address is only implemented on Linux",
+            ))
+        }
+    }
+
+    async fn internal_addresses(&self, filter: Option<String>) -> Result<Vec<IpNet>> {
+        #[cfg(target_os = "linux")]
+        {
+            platform::linux::collect(&self.handle, filter).await
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            PlatformSource::collect(filter.as_deref())
+        }
+    }
+
+    async fn internal_addresses_detailed(
+        &self,
+        filter: Option<String>,
+    ) -> Result<Vec<InterfaceAddr>> {
+        #[cfg(target_os = "linux")]
+        {
+            platform::linux::collect_detailed(&self.handle, filter).await
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            PlatformSource::collect_detailed(filter.as_deref())
+        }
+    }
+}
+
+static DEFAULT: std::sync::OnceLock<LinkAddrs> = std::sync::OnceLock::new();
+
+/// Returns the lazily-created default [`LinkAddrs`] handle backing
+/// the free functions below.
+fn default_handle() -> Result<&'static LinkAddrs> {
+    if let Some(handle) = DEFAULT.get() {
+        return Ok(handle);
+    }
+
+    let handle = LinkAddrs::new()?;
+
+    Ok(DEFAULT.get_or_init(|| handle))
+}
+
 /// Get all IP addresses of an interface.
 pub fn addresses(link: String) -> Result<Vec<IpNet>> {
-    let rt = Runtime::new()?;
-
-    rt.block_on(internal_addresses(Some(link)))
+    default_handle()?.addresses(link)
 }
 
 /// Get the IPv4 addresses of an interface.
 pub fn ipv4_addresses(link: String) -> Result<Vec<Ipv4Net>> {
-    let addrs = addresses(link)?
-        .iter()
-        .filter_map(|addr| match addr {
-            IpNet::V4(addr) => Some(*addr),
-            IpNet::V6(_) => None,
-        })
-        .collect();
-
-    Ok(addrs)
+    default_handle()?.ipv4_addresses(link)
 }
 
 /// Get the IPv6 addresses of an interface.
 pub fn ipv6_addresses(link: String) -> Result<Vec<Ipv6Net>> {
-    let addrs = addresses(link)?
-        .iter()
-        .filter_map(|addr| match addr {
-            IpNet::V4(_) => None,
-            IpNet::V6(addr) => Some(*addr),
-        })
-        .collect();
-
-    Ok(addrs)
+    default_handle()?.ipv6_addresses(link)
 }
 
 /// Get all IP addresses of this host.
 pub fn all_addresses() -> Result<Vec<IpNet>> {
-    let rt = Runtime::new()?;
-
-    rt.block_on(internal_addresses(None))
+    default_handle()?.all_addresses()
 }
 
 /// Get the IPv4 addresses of this host.
 pub fn all_ipv4_addresses() -> Result<Vec<Ipv4Net>> {
-    let addrs = all_addresses()?
-        .iter()
-        .filter_map(|addr| match addr {
-            IpNet::V4(addr) => Some(*addr),
-            IpNet::V6(_) => None,
-        })
-        .collect();
-
-    Ok(addrs)
+    default_handle()?.all_ipv4_addresses()
 }
 
 /// Get the IPv6 addresses of this host.
 pub fn all_ipv6_addresses() -> Result<Vec<Ipv6Net>> {
+    default_handle()?.all_ipv6_addresses()
+}
+
+/// Subscribes to interface address changes: an initial snapshot of
+/// the addresses that exist right now, followed by `Added`/`Removed`
+/// events as interfaces gain or lose addresses via DHCP/SLAAC/manual
+/// configuration.
+///
+/// Unlike the blocking functions in this crate, this doesn't spin up
+/// its own `Runtime` and block on it, since a stream needs to keep being
+/// polled after the call returns; call it from within an existing
+/// tokio context.
+#[cfg(target_os = "linux")]
+pub async fn watch_addresses() -> Result<impl futures::Stream<Item = AddrEvent>> {
+    platform::linux::watch().await
+}
+
+/// Get all addresses of this host that fall into `scope`, e.g. only
+/// the globally-routable ones or only the private RFC1918/ULA ones.
+pub fn addresses_by_scope(scope: AddrScope) -> Result<Vec<InterfaceAddr>> {
+    default_handle()?.addresses_by_scope(scope)
+}
+
+/// Get the IPv6 addresses of an interface, scoped with the
+/// originating interface index so link-local addresses can form a
+/// connectable `SocketAddrV6`.
+pub fn ipv6_addresses_scoped(link: String) -> Result<Vec<(Ipv6Net, u32)>> {
+    default_handle()?.ipv6_addresses_scoped(link)
+}
+
+/// Get the IPv6 addresses of this host, scoped with the originating
+/// interface index so link-local addresses can form a connectable
+/// `SocketAddrV6`.
+pub fn all_ipv6_addresses_scoped() -> Result<Vec<(Ipv6Net, u32)>> {
+    default_handle()?.all_ipv6_addresses_scoped()
+}
+
+/// Get all IP addresses of an interface, plus the interface metadata
+/// that came with them.
+pub fn addresses_detailed(link: String) -> Result<Vec<InterfaceAddr>> {
+    default_handle()?.addresses_detailed(link)
+}
+
+/// Get all IP addresses of this host, plus the interface metadata
+/// that came with them.
+pub fn all_addresses_detailed() -> Result<Vec<InterfaceAddr>> {
+    default_handle()?.all_addresses_detailed()
+}
+
+/// Adds `net` to `link`.
+pub fn add_address(link: String, net: IpNet) -> Result<()> {
+    default_handle()?.add_address(link, net)
+}
+
+/// Removes `net` from `link`.
+pub fn del_address(link: String, net: IpNet) -> Result<()> {
+    default_handle()?.del_address(link, net)
+}
+
+/// Get the host addresses on this machine that fall inside `subnet`.
+pub fn addresses_in(subnet: IpNet) -> Result<Vec<IpNet>> {
     let addrs = all_addresses()?
-        .iter()
-        .filter_map(|addr| match addr {
-            IpNet::V4(_) => None,
-            IpNet::V6(addr) => Some(*addr),
-        })
+        .into_iter()
+        .filter(|addr| subnet.contains(&addr.addr()))
         .collect();
 
     Ok(addrs)
 }
 
-/// Get the IP addresses. If filter is Some, limit the search
-/// to that interface.
-async fn internal_addresses(filter: Option<String>) -> Result<Vec<IpNet>> {
-    let (connection, handle, _) = new_connection()?;
-    tokio::spawn(connection);
-
-    let mut links = handle.link().get();
-
-    if let Some(link) = filter.clone() {
-        links = links.match_name(link);
-    }
-
-    let mut links = links.execute();
-
-    let mut num_links = 0_i32;
-    let mut link_addrs = Vec::new();
-
-    while let Some(link) = links.try_next().await? {
-        let addrs = handle
-            .address()
-            .get()
-            .set_link_index_filter(link.header.index)
-            .execute();
-
-        let addrs = addrs
-            .map_ok(|v| {
-                if let Some(Address(bytes)) = v.nlas.first() {
-                    match v.header.family as u16 {
-                        AF_INET => {
-                            let octets: [u8; 4] = (*bytes).clone().try_into().unwrap();
-                            let ip = IpAddr::from(Ipv4Addr::from(octets));
-                            let net = IpNet::new(ip, v.header.prefix_len).unwrap();
-
-                            Some(net)
-                        }
-                        AF_INET6 => {
-                            let octets: [u8; 16] = (*bytes).clone().try_into().unwrap();
-                            let ip = IpAddr::from(Ipv6Addr::from(octets));
-                            let net = IpNet::new(ip, v.header.prefix_len).unwrap();
-
-                            Some(net)
-                        }
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            })
-            .try_filter(|v| future::ready(v.is_some()))
-            .filter_map(|v| future::ready(v.unwrap()));
-
-        link_addrs.append(&mut addrs.collect::<Vec<IpNet>>().await);
-
-        num_links.add_assign(1);
-    }
+/// Given a subnet this host is attached to, finds the first address
+/// in it that isn't already assigned to an interface.
+pub fn find_usable_host(subnet: IpNet) -> Result<Option<IpAddr>> {
+    let assigned: Vec<IpAddr> = all_addresses()?.into_iter().map(|addr| addr.addr()).collect();
 
-    Ok(link_addrs)
+    Ok(subnet.hosts().find(|host| !assigned.contains(host)))
 }
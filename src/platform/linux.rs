@@ -0,0 +1,252 @@
+//! Linux backend, implemented on top of `rtnetlink`.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use ipnet::IpNet;
+use netlink_packet_route::address::{AddressMessage, Nla as AddressNla};
+use netlink_packet_route::constants::{RTMGRP_IPV4_IFADDR, RTMGRP_IPV6_IFADDR};
+use netlink_packet_route::link::nlas::Nla as LinkNla;
+use netlink_packet_route::rtnl::constants::{
+    AF_INET, AF_INET6, IFF_LOOPBACK, IFF_MULTICAST, IFF_POINTOPOINT, IFF_UP,
+};
+use netlink_packet_route::{NetlinkPayload, RtnlMessage};
+use netlink_sys::{AsyncSocket, SocketAddr as NetlinkSocketAddr};
+use rtnetlink::{new_connection, Handle};
+
+use crate::types::{AddrEvent, InterfaceAddr, InterfaceFlags};
+use crate::{Error, Result};
+
+/// Opens a netlink connection and spawns it onto the current runtime,
+/// returning a [`Handle`] that can be reused across many calls instead
+/// of paying connection setup on every query.
+pub(crate) async fn new_handle() -> Result<Handle> {
+    let (connection, handle, _) = new_connection()?;
+    tokio::spawn(connection);
+
+    Ok(handle)
+}
+
+/// Get the IP addresses. If filter is Some, limit the search
+/// to that interface.
+pub(crate) async fn collect(handle: &Handle, filter: Option<String>) -> Result<Vec<IpNet>> {
+    let addrs = collect_detailed(handle, filter)
+        .await?
+        .into_iter()
+        .map(|addr| addr.net)
+        .collect();
+
+    Ok(addrs)
+}
+
+/// Get the IP addresses plus the interface metadata that came with
+/// them. If filter is Some, limit the search to that interface.
+pub(crate) async fn collect_detailed(
+    handle: &Handle,
+    filter: Option<String>,
+) -> Result<Vec<InterfaceAddr>> {
+    let mut links = handle.link().get();
+
+    if let Some(link) = filter.clone() {
+        links = links.match_name(link);
+    }
+
+    let mut links = links.execute();
+
+    let mut link_addrs = Vec::new();
+
+    while let Some(link) = links.try_next().await? {
+        let index = link.header.index;
+        let flags = InterfaceFlags {
+            up: link.header.flags & IFF_UP != 0,
+            loopback: link.header.flags & IFF_LOOPBACK != 0,
+            point_to_point: link.header.flags & IFF_POINTOPOINT != 0,
+            multicast: link.header.flags & IFF_MULTICAST != 0,
+        };
+
+        let name = link
+            .nlas
+            .iter()
+            .find_map(|nla| match nla {
+                LinkNla::IfName(name) => Some(name.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let mac = link.nlas.iter().find_map(|nla| match nla {
+            LinkNla::Address(bytes) if bytes.len() == 6 => {
+                let mut mac = [0u8; 6];
+                mac.copy_from_slice(bytes);
+                Some(mac)
+            }
+            _ => None,
+        });
+
+        let mut addrs = handle
+            .address()
+            .get()
+            .set_link_index_filter(index)
+            .execute();
+
+        while let Some(addr) = addrs.try_next().await? {
+            let Some((net, broadcast)) = parse_address_message(&addr) else {
+                continue;
+            };
+
+            link_addrs.push(InterfaceAddr {
+                name: name.clone(),
+                index,
+                net,
+                broadcast,
+                flags,
+                mac,
+            });
+        }
+    }
+
+    Ok(link_addrs)
+}
+
+/// Parses the IP/prefix and broadcast address NLAs out of an address
+/// message, shared between the one-shot dump in [`collect_detailed`]
+/// and the multicast notifications in [`watch`].
+fn parse_address_message(msg: &AddressMessage) -> Option<(IpNet, Option<IpAddr>)> {
+    let ip = msg.nlas.iter().find_map(|nla| match nla {
+        AddressNla::Address(bytes) => match msg.header.family as u16 {
+            AF_INET => {
+                let octets: [u8; 4] = (*bytes).clone().try_into().ok()?;
+                Some(IpAddr::from(Ipv4Addr::from(octets)))
+            }
+            AF_INET6 => {
+                let octets: [u8; 16] = (*bytes).clone().try_into().ok()?;
+                Some(IpAddr::from(Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        },
+        _ => None,
+    })?;
+
+    let net = IpNet::new(ip, msg.header.prefix_len).ok()?;
+
+    let broadcast = msg.nlas.iter().find_map(|nla| match nla {
+        AddressNla::Broadcast(bytes) if bytes.len() == 4 => {
+            let octets: [u8; 4] = (*bytes).clone().try_into().ok()?;
+            Some(IpAddr::from(Ipv4Addr::from(octets)))
+        }
+        _ => None,
+    });
+
+    Some((net, broadcast))
+}
+
+/// Watches `RTM_NEWADDR`/`RTM_DELADDR` notifications on the address
+/// multicast groups, emitting an initial snapshot of current
+/// addresses followed by deltas as interfaces gain or lose addresses.
+///
+/// The multicast groups are bound and the connection spawned *before*
+/// the snapshot is dumped, so no add/remove in the window around the
+/// dump can fall in the gap and go unseen; an address that changes
+/// right around the dump may simply be reported twice instead.
+pub(crate) async fn watch() -> Result<impl Stream<Item = AddrEvent>> {
+    let (mut connection, handle, messages) = new_connection()?;
+
+    let groups = RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR;
+    let addr = NetlinkSocketAddr::new(0, groups);
+    connection.socket_mut().socket_mut().bind(&addr)?;
+
+    tokio::spawn(connection);
+
+    let snapshot = collect_detailed(&handle, None)
+        .await?
+        .into_iter()
+        .map(AddrEvent::Added);
+
+    let deltas = messages.filter_map(move |(message, _)| {
+        let event = match message.payload {
+            NetlinkPayload::InnerMessage(RtnlMessage::NewAddress(msg)) => parse_address_message(&msg)
+                .map(|(net, broadcast)| AddrEvent::Added(notified_addr(&msg, net, broadcast))),
+            NetlinkPayload::InnerMessage(RtnlMessage::DelAddress(msg)) => parse_address_message(&msg)
+                .map(|(net, broadcast)| AddrEvent::Removed(notified_addr(&msg, net, broadcast))),
+            _ => None,
+        };
+
+        futures::future::ready(event)
+    });
+
+    Ok(stream::iter(snapshot).chain(deltas))
+}
+
+/// Builds the [`InterfaceAddr`] for a notified address message. Unlike
+/// the dump in [`collect_detailed`], the notification doesn't carry
+/// the link header, so flags and the MAC address aren't available
+/// here; the interface name comes from `IFA_LABEL` instead.
+fn notified_addr(msg: &AddressMessage, net: IpNet, broadcast: Option<IpAddr>) -> InterfaceAddr {
+    let name = msg
+        .nlas
+        .iter()
+        .find_map(|nla| match nla {
+            AddressNla::Label(label) => Some(label.clone()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    InterfaceAddr {
+        name,
+        index: msg.header.index,
+        net,
+        broadcast,
+        flags: InterfaceFlags::default(),
+        mac: None,
+    }
+}
+
+/// Adds `net` to `link`.
+pub(crate) async fn add_address(handle: &Handle, link: String, net: IpNet) -> Result<()> {
+    let index = resolve_index(handle, &link).await?;
+
+    handle
+        .address()
+        .add(index, net.addr(), net.prefix_len())
+        .execute()
+        .await?;
+
+    Ok(())
+}
+
+/// Removes `net` from `link`.
+pub(crate) async fn del_address(handle: &Handle, link: String, net: IpNet) -> Result<()> {
+    let index = resolve_index(handle, &link).await?;
+
+    let mut addrs = handle
+        .address()
+        .get()
+        .set_link_index_filter(index)
+        .execute();
+
+    while let Some(addr) = addrs.try_next().await? {
+        if parse_address_message(&addr).is_some_and(|(existing, _)| existing == net) {
+            handle.address().del(addr).execute().await?;
+            return Ok(());
+        }
+    }
+
+    Err(Error::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("{net} is not assigned to {link}"),
+    )))
+}
+
+/// Resolves the interface index of `link` by name, the same way
+/// [`collect_detailed`] filters the link dump.
+async fn resolve_index(handle: &Handle, link: &str) -> Result<u32> {
+    let mut links = handle.link().get().match_name(link.to_string()).execute();
+
+    let Some(link_msg) = links.try_next().await? else {
+        return Err(Error::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no such interface: {link}"),
+        )));
+    };
+
+    Ok(link_msg.header.index)
+}
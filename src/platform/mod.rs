@@ -0,0 +1,54 @@
+//! Platform-specific address collection backends.
+//!
+//! [`LinkAddrs::internal_addresses`](crate::LinkAddrs) and
+//! `internal_addresses_detailed` dispatch to one of these at compile
+//! time via `cfg(target_os = ...)`, so the public API in `lib.rs` stays
+//! identical regardless of which mechanism (netlink, `getifaddrs(3)`,
+//! `GetAdaptersAddresses`) actually produced the result.
+
+use ipnet::IpNet;
+
+use crate::types::InterfaceAddr;
+use crate::Result;
+
+#[cfg(target_os = "linux")]
+pub(crate) mod linux;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+mod unix;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+/// A backend that can enumerate the [`IpNet`]s assigned to one or all
+/// interfaces on the host.
+///
+/// Implemented once per non-Linux platform; the Linux backend stays a
+/// plain async fn in [`linux`] since it is driven by an existing
+/// netlink connection rather than a blocking syscall.
+pub(crate) trait AddressSource {
+    /// Collects addresses, optionally restricted to `filter`.
+    fn collect(filter: Option<&str>) -> Result<Vec<IpNet>>;
+
+    /// Collects addresses plus the interface metadata that came with
+    /// them, optionally restricted to `filter`.
+    fn collect_detailed(filter: Option<&str>) -> Result<Vec<InterfaceAddr>>;
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+pub(crate) use unix::GetifaddrsSource as PlatformSource;
+
+#[cfg(target_os = "windows")]
+pub(crate) use windows::AdaptersSource as PlatformSource;
@@ -0,0 +1,97 @@
+//! macOS/BSD backend, implemented on top of `getifaddrs(3)`.
+
+use std::collections::HashMap;
+
+use ipnet::IpNet;
+use nix::ifaddrs::getifaddrs;
+use nix::net::if_::InterfaceFlags as NixFlags;
+use nix::sys::socket::SockAddr;
+
+use crate::platform::AddressSource;
+use crate::types::{InterfaceAddr, InterfaceFlags};
+use crate::Result;
+
+pub(crate) struct GetifaddrsSource;
+
+impl AddressSource for GetifaddrsSource {
+    fn collect(filter: Option<&str>) -> Result<Vec<IpNet>> {
+        let addrs = Self::collect_detailed(filter)?
+            .into_iter()
+            .map(|addr| addr.net)
+            .collect();
+
+        Ok(addrs)
+    }
+
+    fn collect_detailed(filter: Option<&str>) -> Result<Vec<InterfaceAddr>> {
+        // `getifaddrs` reports one entry per interface address, so the
+        // link-layer address shows up as its own AF_LINK entry. Collect
+        // those first so they can be attached to the AF_INET/AF_INET6
+        // entries for the same interface.
+        let mut macs: HashMap<String, [u8; 6]> = HashMap::new();
+
+        for iface in getifaddrs()? {
+            if let Some(SockAddr::Link(link)) = iface.address {
+                let octets = link.addr();
+                if octets.len() == 6 {
+                    let mut mac = [0u8; 6];
+                    mac.copy_from_slice(&octets);
+                    macs.insert(iface.interface_name, mac);
+                }
+            }
+        }
+
+        let mut addrs = Vec::new();
+
+        for iface in getifaddrs()? {
+            if let Some(link) = filter {
+                if iface.interface_name != link {
+                    continue;
+                }
+            }
+
+            let (ip, mask) = match (iface.address, iface.netmask) {
+                (Some(SockAddr::Inet(addr)), Some(SockAddr::Inet(mask))) => {
+                    (addr.ip().to_std(), mask.ip().to_std())
+                }
+                _ => continue,
+            };
+
+            let prefix_len = match (ip, mask) {
+                (std::net::IpAddr::V4(_), std::net::IpAddr::V4(mask)) => {
+                    u32::from(mask).count_ones() as u8
+                }
+                (std::net::IpAddr::V6(_), std::net::IpAddr::V6(mask)) => {
+                    u128::from(mask).count_ones() as u8
+                }
+                _ => continue,
+            };
+
+            let Ok(net) = IpNet::new(ip, prefix_len) else {
+                continue;
+            };
+
+            let broadcast = iface.broadcast.and_then(|b| match b {
+                SockAddr::Inet(b) => Some(b.ip().to_std()),
+                _ => None,
+            });
+
+            addrs.push(InterfaceAddr {
+                name: iface.interface_name.clone(),
+                index: nix::net::if_::if_nametoindex(iface.interface_name.as_str())
+                    .unwrap_or_default(),
+                net,
+                broadcast,
+                flags: InterfaceFlags {
+                    up: iface.flags.contains(NixFlags::IFF_UP),
+                    loopback: iface.flags.contains(NixFlags::IFF_LOOPBACK),
+                    point_to_point: iface.flags.contains(NixFlags::IFF_POINTOPOINT),
+                    multicast: iface.flags.contains(NixFlags::IFF_MULTICAST),
+                },
+                mac: macs.get(&iface.interface_name).copied(),
+            });
+        }
+
+        Ok(addrs)
+    }
+}
@@ -0,0 +1,140 @@
+//! Windows backend, implemented on top of `GetAdaptersAddresses`.
+
+use std::net::IpAddr;
+use std::ptr;
+
+use ipnet::IpNet;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetAdaptersAddresses, IP_ADAPTER_ADDRESSES_LH, IP_ADAPTER_UNICAST_ADDRESS_LH,
+    GAA_FLAG_INCLUDE_PREFIX, IF_TYPE_PPP, IF_TYPE_SOFTWARE_LOOPBACK,
+};
+use windows_sys::Win32::Networking::WinSock::{
+    IfOperStatusUp, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6,
+};
+
+use crate::platform::AddressSource;
+use crate::types::{InterfaceAddr, InterfaceFlags};
+use crate::{Error, Result};
+
+pub(crate) struct AdaptersSource;
+
+impl AddressSource for AdaptersSource {
+    fn collect(filter: Option<&str>) -> Result<Vec<IpNet>> {
+        let addrs = Self::collect_detailed(filter)?
+            .into_iter()
+            .map(|addr| addr.net)
+            .collect();
+
+        Ok(addrs)
+    }
+
+    fn collect_detailed(filter: Option<&str>) -> Result<Vec<InterfaceAddr>> {
+        let mut size: u32 = 16 * 1024;
+        let mut buf: Vec<u8>;
+
+        loop {
+            buf = vec![0; size as usize];
+
+            let ret = unsafe {
+                GetAdaptersAddresses(
+                    AF_UNSPEC as u32,
+                    GAA_FLAG_INCLUDE_PREFIX,
+                    ptr::null_mut(),
+                    buf.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH,
+                    &mut size,
+                )
+            };
+
+            match ret {
+                0 => break,
+                111 /* ERROR_BUFFER_OVERFLOW */ => continue,
+                e => return Err(Error::IoError(std::io::Error::from_raw_os_error(e as i32))),
+            }
+        }
+
+        let mut addrs = Vec::new();
+        let mut adapter = buf.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+
+        while !adapter.is_null() {
+            let info = unsafe { &*adapter };
+            let name = unsafe { widestring_to_string(info.FriendlyName) };
+
+            if filter.is_none_or(|link| link == name) {
+                let mac = if info.PhysicalAddressLength == 6 {
+                    let mut mac = [0u8; 6];
+                    mac.copy_from_slice(&info.PhysicalAddress[..6]);
+                    Some(mac)
+                } else {
+                    None
+                };
+
+                let flags = InterfaceFlags {
+                    up: info.OperStatus == IfOperStatusUp,
+                    loopback: info.IfType == IF_TYPE_SOFTWARE_LOOPBACK,
+                    point_to_point: info.IfType == IF_TYPE_PPP,
+                    multicast: info.Flags & windows_sys::Win32::NetworkManagement::IpHelper::IP_ADAPTER_NO_MULTICAST == 0,
+                };
+
+                let mut unicast = info.FirstUnicastAddress as *const IP_ADAPTER_UNICAST_ADDRESS_LH;
+
+                while !unicast.is_null() {
+                    let ua = unsafe { &*unicast };
+                    let prefix_len = ua.OnLinkPrefixLength;
+                    let sockaddr = ua.Address.lpSockaddr;
+
+                    let ip = unsafe {
+                        match (*sockaddr).sa_family as i32 {
+                            x if x == windows_sys::Win32::Networking::WinSock::AF_INET as i32 => {
+                                let sin = &*(sockaddr as *const SOCKADDR_IN);
+                                Some(IpAddr::from(sin.sin_addr.S_un.S_addr.to_ne_bytes()))
+                            }
+                            x if x == windows_sys::Win32::Networking::WinSock::AF_INET6 as i32 => {
+                                let sin6 = &*(sockaddr as *const SOCKADDR_IN6);
+                                Some(IpAddr::from(sin6.sin6_addr.u.Byte))
+                            }
+                            _ => None,
+                        }
+                    };
+
+                    if let Some(ip) = ip {
+                        if let Ok(net) = IpNet::new(ip, prefix_len) {
+                            let (broadcast, index) = match net {
+                                IpNet::V4(v4) => (Some(IpAddr::V4(v4.broadcast())), info.IfIndex),
+                                IpNet::V6(_) => (None, info.Ipv6IfIndex),
+                            };
+
+                            addrs.push(InterfaceAddr {
+                                name: name.clone(),
+                                index,
+                                net,
+                                broadcast,
+                                flags,
+                                mac,
+                            });
+                        }
+                    }
+
+                    unicast = ua.Next;
+                }
+            }
+
+            adapter = info.Next;
+        }
+
+        Ok(addrs)
+    }
+}
+
+unsafe fn widestring_to_string(ptr: *mut u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+
+    let slice = std::slice::from_raw_parts(ptr, len);
+    String::from_utf16_lossy(slice)
+}
@@ -0,0 +1,90 @@
+//! Richer address records returned by the `_detailed` query functions.
+
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+
+/// Up/loopback/point-to-point/multicast flags of an interface, as
+/// reported by the link header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceFlags {
+    pub up: bool,
+    pub loopback: bool,
+    pub point_to_point: bool,
+    pub multicast: bool,
+}
+
+/// A single interface address plus the interface metadata that came
+/// with it, instead of just the bare `IpNet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceAddr {
+    pub name: String,
+    pub index: u32,
+    pub net: IpNet,
+    pub broadcast: Option<IpAddr>,
+    pub flags: InterfaceFlags,
+    pub mac: Option<[u8; 6]>,
+}
+
+impl InterfaceAddr {
+    /// Classifies this address's reachability, refined by whether it
+    /// came from a loopback interface.
+    pub fn scope(&self) -> AddrScope {
+        AddrScope::of(&self.net.addr(), self.flags.loopback)
+    }
+}
+
+/// Coarse reachability classification of an address, e.g. to decide
+/// which discovered addresses are worth advertising to peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrScope {
+    Loopback,
+    LinkLocal,
+    Private,
+    Global,
+}
+
+impl AddrScope {
+    /// Classifies `addr`, optionally refined by whether it came from
+    /// a loopback interface.
+    pub fn of(addr: &IpAddr, is_loopback_iface: bool) -> Self {
+        if is_loopback_iface || is_loopback(addr) {
+            Self::Loopback
+        } else if is_link_local(addr) {
+            Self::LinkLocal
+        } else if is_private(addr) {
+            Self::Private
+        } else {
+            Self::Global
+        }
+    }
+}
+
+fn is_loopback(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => ip.is_loopback(),
+        IpAddr::V6(ip) => ip.is_loopback(),
+    }
+}
+
+fn is_link_local(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => ip.is_link_local(),
+        IpAddr::V6(ip) => (ip.segments()[0] & 0xffc0) == 0xfe80,
+    }
+}
+
+fn is_private(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => ip.is_private(),
+        IpAddr::V6(ip) => (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// An address gained or lost by an interface, as reported by
+/// [`crate::watch_addresses`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddrEvent {
+    Added(InterfaceAddr),
+    Removed(InterfaceAddr),
+}
@@ -1,4 +1,6 @@
-use linkaddrs::Result;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use linkaddrs::{AddrScope, Result};
 
 #[test]
 fn test_addresses() -> Result<()> {
@@ -31,3 +33,96 @@ fn test_all_addresses() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_addresses_detailed() -> Result<()> {
+    let addrs = linkaddrs::addresses_detailed(String::from("lo"))?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[test]
+fn test_ipv6_addresses_scoped() -> Result<()> {
+    let addrs = linkaddrs::ipv6_addresses_scoped(String::from("lo"))?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[test]
+fn test_linkaddrs() -> Result<()> {
+    let client = linkaddrs::LinkAddrs::new()?;
+
+    let addrs = client.addresses(String::from("lo"))?;
+    println!("{:?}", addrs);
+
+    let addrs = client.all_addresses_detailed()?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_linkaddrs_new_async() -> Result<()> {
+    let client = linkaddrs::LinkAddrs::new_async().await?;
+
+    let addrs = client.addresses_async(String::from("lo")).await?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[test]
+fn test_add_del_address() -> Result<()> {
+    let net = "203.0.113.1/32".parse().unwrap();
+
+    linkaddrs::add_address(String::from("lo"), net)?;
+    linkaddrs::del_address(String::from("lo"), net)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_addr_scope_of() {
+    let loopback_v4 = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let loopback_v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+    let link_local = IpAddr::V4(Ipv4Addr::new(169, 254, 1, 1));
+    let private = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    let global = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+
+    assert_eq!(AddrScope::of(&loopback_v4, false), AddrScope::Loopback);
+    assert_eq!(AddrScope::of(&loopback_v6, false), AddrScope::Loopback);
+    assert_eq!(AddrScope::of(&global, true), AddrScope::Loopback);
+    assert_eq!(AddrScope::of(&link_local, false), AddrScope::LinkLocal);
+    assert_eq!(AddrScope::of(&private, false), AddrScope::Private);
+    assert_eq!(AddrScope::of(&global, false), AddrScope::Global);
+}
+
+#[test]
+fn test_addresses_by_scope() -> Result<()> {
+    let addrs = linkaddrs::addresses_by_scope(AddrScope::Loopback)?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[test]
+fn test_addresses_in() -> Result<()> {
+    let subnet = "127.0.0.0/8".parse().unwrap();
+
+    let addrs = linkaddrs::addresses_in(subnet)?;
+    println!("{:?}", addrs);
+
+    Ok(())
+}
+
+#[test]
+fn test_find_usable_host() -> Result<()> {
+    let subnet = "127.0.0.0/8".parse().unwrap();
+
+    let host = linkaddrs::find_usable_host(subnet)?;
+    println!("{:?}", host);
+
+    Ok(())
+}